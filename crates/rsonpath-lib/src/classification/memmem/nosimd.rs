@@ -4,6 +4,7 @@ use crate::input::{Input, InputBlockIterator};
 use crate::query::JsonString;
 use crate::result::InputRecorder;
 use crate::FallibleIterator;
+use memchr::memchr_iter;
 
 pub(crate) struct SequentialMemmemClassifier<'i, 'b, 'r, I, R, const N: usize>
 where
@@ -35,13 +36,15 @@ where
         let first_c = label.bytes()[0];
 
         while let Some(block) = self.iter.next()? {
-            let res = block.iter().copied().enumerate().find(|&(i, c)| {
+            // Jump directly to each candidate `first_c` byte via a SIMD byte
+            // search instead of comparing every byte of the block; the label
+            // itself is only checked with `is_member_match` at those candidates.
+            for i in memchr_iter(first_c, &block) {
                 let j = offset + i;
-                c == first_c && self.input.is_member_match(j - 1, j + label_size - 2, label)
-            });
 
-            if let Some((res, _)) = res {
-                return Ok(Some((res + offset - 1, block)));
+                if self.input.is_member_match(j - 1, j + label_size - 2, label) {
+                    return Ok(Some((i + offset - 1, block)));
+                }
             }
 
             offset += block.len();
@@ -0,0 +1,168 @@
+//! Input implementation that reads its source incrementally, buffering only
+//! as much as has been asked for so far.
+//!
+//! Choose this implementation if the input is a stream you want to start
+//! querying without waiting to read it in full, and a memory map is not
+//! available.
+
+use super::{error::InputError, in_slice, FileOrStdin, Input, InputBlockIterator, MAX_BLOCK_SIZE};
+use crate::{query::JsonString, FallibleIterator};
+use std::cell::{Cell, RefCell};
+use std::io::Read;
+
+/// Input reading lazily from a [`FileOrStdin`] source into a growing internal buffer.
+pub struct BufferedInput {
+    source: RefCell<FileOrStdin>,
+    buffer: RefCell<Vec<u8>>,
+    exhausted: Cell<bool>,
+}
+
+impl BufferedInput {
+    /// Wrap `source` for incremental, buffered reading.
+    #[must_use]
+    #[inline]
+    pub fn new(source: FileOrStdin) -> Self {
+        Self {
+            source: RefCell::new(source),
+            buffer: RefCell::new(Vec::new()),
+            exhausted: Cell::new(false),
+        }
+    }
+
+    /// Make sure at least `len` bytes are buffered, reading more from the
+    /// source as needed. Once the source is exhausted this is a no-op.
+    fn fill_to(&self, len: usize) -> Result<(), InputError> {
+        let mut buffer = self.buffer.borrow_mut();
+        if self.exhausted.get() || buffer.len() >= len {
+            return Ok(());
+        }
+
+        let mut source = self.source.borrow_mut();
+        let mut chunk = vec![0; len - buffer.len()];
+        let mut filled = 0;
+
+        while filled < chunk.len() {
+            let read = source.read(&mut chunk[filled..])?;
+            if read == 0 {
+                self.exhausted.set(true);
+                break;
+            }
+            filled += read;
+        }
+
+        chunk.truncate(filled);
+        buffer.extend_from_slice(&chunk);
+
+        Ok(())
+    }
+}
+
+impl Input for BufferedInput {
+    type BlockIterator<'a, const N: usize> = BufferedInputBlockIterator<'a, N>;
+
+    #[inline(always)]
+    fn iter_blocks<const N: usize>(&self) -> Self::BlockIterator<'_, N> {
+        BufferedInputBlockIterator::new(self)
+    }
+
+    #[inline]
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize> {
+        self.fill_to(from + 1).ok()?;
+        in_slice::seek_backward(&self.buffer.borrow(), from, needle)
+    }
+
+    #[inline]
+    fn seek_non_whitespace_forward(&self, from: usize) -> Result<Option<(usize, u8)>, InputError> {
+        // Whitespace can only be confirmed absent once we've read past it,
+        // so keep growing the buffer until we find a non-whitespace byte or
+        // run out of input.
+        loop {
+            self.fill_to(self.buffer.borrow().len() + MAX_BLOCK_SIZE)?;
+            let buffer = self.buffer.borrow();
+
+            if let Some(res) = in_slice::seek_non_whitespace_forward(&buffer, from) {
+                return Ok(Some(res));
+            }
+            if self.exhausted.get() {
+                return Ok(None);
+            }
+        }
+    }
+
+    #[inline]
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)> {
+        self.fill_to(from + 1).ok()?;
+        in_slice::seek_non_whitespace_backward(&self.buffer.borrow(), from)
+    }
+
+    #[inline]
+    #[cfg(feature = "head-skip")]
+    fn find_member(&self, from: usize, label: &JsonString) -> Result<Option<usize>, InputError> {
+        loop {
+            self.fill_to(self.buffer.borrow().len() + MAX_BLOCK_SIZE)?;
+            let buffer = self.buffer.borrow();
+
+            if let Some(res) = in_slice::find_member(&buffer, from, label) {
+                return Ok(Some(res));
+            }
+            if self.exhausted.get() {
+                return Ok(None);
+            }
+        }
+    }
+
+    #[inline]
+    fn is_member_match(&self, from: usize, to: usize, label: &JsonString) -> bool {
+        if self.fill_to(to + 1).is_err() {
+            return false;
+        }
+        in_slice::is_member_match(&self.buffer.borrow(), from, to, label)
+    }
+}
+
+/// Iterator over blocks of [`BufferedInput`] of size exactly `N`, read lazily
+/// and padded with zeroes once the source is exhausted.
+pub struct BufferedInputBlockIterator<'a, const N: usize> {
+    input: &'a BufferedInput,
+    idx: usize,
+}
+
+impl<'a, const N: usize> BufferedInputBlockIterator<'a, N> {
+    #[must_use]
+    #[inline(always)]
+    pub(super) fn new(input: &'a BufferedInput) -> Self {
+        Self { input, idx: 0 }
+    }
+}
+
+impl<'a, const N: usize> FallibleIterator for BufferedInputBlockIterator<'a, N> {
+    type Item = Box<[u8]>;
+    type Error = InputError;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        self.input.fill_to(self.idx + N)?;
+
+        let buffer = self.input.buffer.borrow();
+        if self.idx >= buffer.len() {
+            return Ok(None);
+        }
+
+        let end = (self.idx + N).min(buffer.len());
+        let mut block = vec![0; N];
+        block[..end - self.idx].copy_from_slice(&buffer[self.idx..end]);
+        self.idx += N;
+
+        Ok(Some(block.into_boxed_slice()))
+    }
+}
+
+impl<'a, const N: usize> InputBlockIterator<'a, N> for BufferedInputBlockIterator<'a, N> {
+    type Block = Box<[u8]>;
+
+    #[inline(always)]
+    fn offset(&mut self, count: isize) {
+        assert!(count >= 0);
+        self.idx += count as usize * N;
+    }
+}
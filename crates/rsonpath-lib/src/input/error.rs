@@ -0,0 +1,11 @@
+//! Errors raised while resolving or reading an [`Input`](super::Input).
+
+use thiserror::Error;
+
+/// Error type for operations on [`Input`](super::Input) implementations.
+#[derive(Debug, Error)]
+pub enum InputError {
+    /// An I/O error occurred while reading or mapping the input.
+    #[error("an I/O error occurred while handling the input: {0}")]
+    Io(#[from] std::io::Error),
+}
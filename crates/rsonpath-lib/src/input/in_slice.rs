@@ -0,0 +1,38 @@
+//! Shared helpers for implementing [`Input`](super::Input) directly over an
+//! in-memory byte slice; used by both [`MmapInput`](super::MmapInput) and
+//! [`OwnedBytes`](super::OwnedBytes).
+
+use crate::query::JsonString;
+
+#[inline]
+pub(crate) fn seek_backward(bytes: &[u8], from: usize, needle: u8) -> Option<usize> {
+    bytes[..=from].iter().rposition(|&b| b == needle)
+}
+
+#[inline]
+pub(crate) fn seek_non_whitespace_forward(bytes: &[u8], from: usize) -> Option<(usize, u8)> {
+    bytes[from..]
+        .iter()
+        .position(|&b| !b.is_ascii_whitespace())
+        .map(|i| (from + i, bytes[from + i]))
+}
+
+#[inline]
+pub(crate) fn seek_non_whitespace_backward(bytes: &[u8], from: usize) -> Option<(usize, u8)> {
+    bytes[..=from]
+        .iter()
+        .rposition(|&b| !b.is_ascii_whitespace())
+        .map(|i| (i, bytes[i]))
+}
+
+#[cfg(feature = "head-skip")]
+#[inline]
+pub(crate) fn find_member(bytes: &[u8], from: usize, label: &JsonString) -> Option<usize> {
+    let needle = label.bytes_with_quotes();
+    bytes[from..].windows(needle.len()).position(|w| w == needle).map(|i| from + i)
+}
+
+#[inline]
+pub(crate) fn is_member_match(bytes: &[u8], from: usize, to: usize, label: &JsonString) -> bool {
+    bytes.get(from..=to) == Some(label.bytes_with_quotes())
+}
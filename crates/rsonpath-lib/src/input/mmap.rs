@@ -16,6 +16,7 @@
 //! file into main memory.
 
 use std::fs::File;
+use std::io::Read;
 
 use super::{error::InputError, in_slice, Input, InputBlockIterator, MAX_BLOCK_SIZE};
 use crate::{query::JsonString, FallibleIterator};
@@ -26,6 +27,13 @@ pub struct MmapInput {
     mmap: Mmap,
 }
 
+/// How many bytes of the start of a mapping to eagerly prefault with
+/// `MADV_WILLNEED`. Large enough to cover the first few blocks the
+/// classifier will touch immediately, small enough not to defeat the
+/// stall-reduction `MADV_SEQUENTIAL` is meant to provide on a large file.
+#[cfg(unix)]
+const WILL_NEED_PREFETCH_SPAN: usize = 64 * MAX_BLOCK_SIZE;
+
 impl MmapInput {
     /// Map a file to memory.
     ///
@@ -51,6 +59,101 @@ impl MmapInput {
             Err(err) => Err(err.into()),
         }
     }
+
+    /// Map a file to memory, advising the kernel that the mapping will be
+    /// accessed sequentially from start to end.
+    ///
+    /// This is the access pattern rsonpath's block iterators always follow, so
+    /// advising the kernel lets it read ahead more aggressively and drop pages
+    /// we've already scanned past, which cuts down on page-fault stalls for
+    /// large files. On platforms without `madvise` support this is equivalent
+    /// to [`map_file`](MmapInput::map_file).
+    ///
+    /// # Safety
+    ///
+    /// See [`map_file`](MmapInput::map_file).
+    ///
+    /// # Errors
+    ///
+    /// Calling mmap might result in an IO error. A failure to advise the
+    /// kernel is not treated as an error, since the advice is only a hint.
+    #[inline]
+    pub unsafe fn map_file_with_advice(file: &File) -> Result<Self, InputError> {
+        let input = Self::map_file(file)?;
+        input.advise_sequential();
+        Ok(input)
+    }
+
+    #[cfg(unix)]
+    #[inline]
+    fn advise_sequential(&self) {
+        use memmap2::Advice;
+
+        // Advice is only a hint; if the kernel rejects it we just fall back
+        // to the default access pattern, so the error is silently ignored.
+        let _ = self.mmap.advise(Advice::Sequential);
+
+        // Only prefault the first span rather than the whole mapping: eagerly
+        // faulting in every page here would work against what `Sequential`
+        // is for, namely letting the kernel read ahead and drop pages behind
+        // the scan instead of paying for the whole file up front.
+        let prefetch_len = self.mmap.len().min(WILL_NEED_PREFETCH_SPAN);
+        let _ = self.mmap.advise_range(Advice::WillNeed, 0, prefetch_len);
+    }
+
+    #[cfg(not(unix))]
+    #[inline(always)]
+    fn advise_sequential(&self) {}
+
+    /// Copy `bytes` into a new anonymous, private memory mapping, so that the
+    /// rest of the engine can still enjoy the fast mmap path even when the
+    /// source couldn't be memory-mapped directly (typically piped standard
+    /// input).
+    ///
+    /// Split out of [`from_bytes_anonymous`](MmapInput::from_bytes_anonymous)
+    /// so that a caller which already holds the bytes in hand (for example,
+    /// after reading them for some other purpose) can build the mapping
+    /// without paying for another read, and can still recover the bytes if
+    /// this fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the anonymous mapping cannot be created.
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, InputError> {
+        let rem = bytes.len() % MAX_BLOCK_SIZE;
+        let pad = if rem == 0 { 0 } else { MAX_BLOCK_SIZE - rem };
+
+        let mut mmap = MmapOptions::new().len(bytes.len() + pad).map_anon()?;
+        // The tail past `bytes.len()` is left at the anonymous mapping's
+        // implicit zero-fill, matching the padding a file-backed map gets.
+        mmap[..bytes.len()].copy_from_slice(bytes);
+
+        let mmap = mmap.make_read_only()?;
+
+        Ok(Self { mmap })
+    }
+
+    /// Drain a reader that cannot itself be memory-mapped (typically piped
+    /// standard input) into an anonymous, private memory mapping, so that the
+    /// rest of the engine can still enjoy the fast mmap path.
+    ///
+    /// The reader is fully consumed into a block-aligned mapping, preserving
+    /// the same zero-padding invariant [`map_file`](MmapInput::map_file) gives
+    /// file-backed mappings, which [`MmapBlockIterator::next`] relies on when
+    /// it slices past the logical end of the input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from `reader` fails, or if the anonymous
+    /// mapping cannot be created.
+    #[inline]
+    pub fn from_bytes_anonymous<R: Read>(mut reader: R) -> Result<Self, InputError> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf)?;
+
+        Self::from_bytes(&buf)
+    }
 }
 
 impl Input for MmapInput {
@@ -128,3 +231,27 @@ impl<'a, const N: usize> InputBlockIterator<'a, N> for MmapBlockIterator<'a, N>
         self.idx += count as usize * N;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_bytes_anonymous_pads_to_a_block_boundary_with_zeroes() {
+        let contents = b"{\"needs padding\":true}".to_vec();
+        let input = MmapInput::from_bytes_anonymous(contents.as_slice()).expect("mapping the bytes failed");
+
+        assert_eq!(input.mmap.len() % MAX_BLOCK_SIZE, 0);
+        assert!(input.mmap.starts_with(&contents));
+        assert!(input.mmap[contents.len()..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn from_bytes_anonymous_of_an_already_aligned_input_adds_no_padding() {
+        let contents = vec![b'a'; MAX_BLOCK_SIZE];
+        let input = MmapInput::from_bytes_anonymous(contents.as_slice()).expect("mapping the bytes failed");
+
+        assert_eq!(input.mmap.len(), MAX_BLOCK_SIZE);
+        assert_eq!(&input.mmap[..], &contents[..]);
+    }
+}
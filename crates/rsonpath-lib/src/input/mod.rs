@@ -0,0 +1,257 @@
+//! Input handling for the engine, abstracting over the different ways the raw
+//! bytes of a JSON document can be made available to the classification pipeline.
+//!
+//! See the individual submodules for the available [`Input`] implementations
+//! and their performance characteristics.
+
+pub mod buffered;
+pub mod error;
+pub(crate) mod in_slice;
+pub mod mmap;
+pub mod owned;
+
+use error::InputError;
+use std::fs::File;
+use std::io::{self, Read};
+
+use crate::{query::JsonString, FallibleIterator};
+
+pub use buffered::BufferedInput;
+pub use mmap::MmapInput;
+pub use owned::OwnedBytes;
+
+/// The size, in bytes, of a single block the classifiers operate on.
+pub const MAX_BLOCK_SIZE: usize = 64;
+
+/// Below this size, in bytes, the overhead of setting up a memory map outweighs
+/// any benefit it gives over simply reading the file into memory.
+///
+/// Sixteen 4 KiB pages is a common rule of thumb for where `mmap` starts to pay for itself;
+/// rsonpath queries frequently target many small JSON documents, so the fallback
+/// matters in practice.
+const MMAP_SIZE_THRESHOLD: u64 = 16 * 4096;
+
+/// A source of bytes the classification pipeline can run a query against.
+pub trait Input {
+    /// Iterator over blocks of the input of a given size.
+    type BlockIterator<'a, const N: usize>: InputBlockIterator<'a, N>
+    where
+        Self: 'a;
+
+    /// Return an iterator over blocks of size `N` of the input.
+    fn iter_blocks<const N: usize>(&self) -> Self::BlockIterator<'_, N>;
+
+    /// Find the closest `needle` at or before the `from` position, searching backwards.
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize>;
+
+    /// Find the first non-whitespace byte at or after the `from` position.
+    ///
+    /// # Errors
+    /// May fail if reading the input fails.
+    fn seek_non_whitespace_forward(&self, from: usize) -> Result<Option<(usize, u8)>, InputError>;
+
+    /// Find the first non-whitespace byte at or before the `from` position, searching backwards.
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)>;
+
+    /// Find the first occurrence of a member `label` at or after the `from` position.
+    ///
+    /// # Errors
+    /// May fail if reading the input fails.
+    #[cfg(feature = "head-skip")]
+    fn find_member(&self, from: usize, label: &JsonString) -> Result<Option<usize>, InputError>;
+
+    /// Check whether the bytes in the `from..=to` range match the member `label`.
+    fn is_member_match(&self, from: usize, to: usize, label: &JsonString) -> bool;
+}
+
+/// A [`FallibleIterator`] over blocks of an [`Input`] of a given size.
+pub trait InputBlockIterator<'a, const N: usize>: FallibleIterator<Error = InputError> {
+    /// The type of a single block returned by this iterator.
+    type Block;
+
+    /// Skip `count` blocks forward.
+    fn offset(&mut self, count: isize);
+}
+
+/// Source of the bytes to run a query against: either an opened file or the
+/// standard input stream.
+pub enum FileOrStdin {
+    /// Input is read from an opened file.
+    File(File),
+    /// Input is read from standard input.
+    Stdin(PeekableStdin),
+}
+
+impl Read for FileOrStdin {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::File(file) => file.read(buf),
+            Self::Stdin(stdin) => stdin.read(buf),
+        }
+    }
+}
+
+/// Standard input, wrapped so that a handful of bytes already consumed for
+/// sniffing purposes (e.g. detecting a compression header) can be replayed
+/// before the rest of the stream: unlike a file, stdin cannot be seeked back
+/// to the start once read.
+pub struct PeekableStdin {
+    prefix: Vec<u8>,
+    prefix_pos: usize,
+    stdin: io::Stdin,
+}
+
+impl PeekableStdin {
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            prefix: Vec::new(),
+            prefix_pos: 0,
+            stdin: io::stdin(),
+        }
+    }
+
+    /// Read up to `len` bytes from the stream without losing them: this call
+    /// and every subsequent read (including through [`Read::read`]) see
+    /// these bytes again before continuing into the rest of the stream.
+    ///
+    /// # Errors
+    /// May fail if reading from stdin fails.
+    pub fn peek(&mut self, len: usize) -> io::Result<&[u8]> {
+        while self.prefix.len() < len {
+            let mut chunk = vec![0; len - self.prefix.len()];
+            let read = self.stdin.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            self.prefix.extend_from_slice(&chunk[..read]);
+        }
+
+        Ok(&self.prefix)
+    }
+}
+
+impl Default for PeekableStdin {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Read for PeekableStdin {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.prefix_pos < self.prefix.len() {
+            let len = (self.prefix.len() - self.prefix_pos).min(buf.len());
+            buf[..len].copy_from_slice(&self.prefix[self.prefix_pos..self.prefix_pos + len]);
+            self.prefix_pos += len;
+            Ok(len)
+        } else {
+            self.stdin.read(buf)
+        }
+    }
+}
+
+/// The input strategy to use to read and hold the document's bytes in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolvedInputKind {
+    /// Memory-map the input, if the platform and source support it.
+    Mmap,
+    /// Read the entire input into an owned, contiguous buffer.
+    Owned,
+    /// Read the input incrementally through a buffered reader.
+    Buffered,
+}
+
+/// A user-requested input strategy, forcing `decide_input_strategy` to use a
+/// specific [`ResolvedInputKind`] instead of picking one automatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputArg {
+    /// Force the memory-mapped input.
+    Mmap,
+    /// Force reading the whole input into an owned buffer.
+    Owned,
+    /// Force the buffered, streaming input.
+    Buffered,
+}
+
+impl From<&InputArg> for ResolvedInputKind {
+    #[inline]
+    fn from(value: &InputArg) -> Self {
+        match value {
+            InputArg::Mmap => Self::Mmap,
+            InputArg::Owned => Self::Owned,
+            InputArg::Buffered => Self::Buffered,
+        }
+    }
+}
+
+/// Decide which [`ResolvedInputKind`] to use for the given input source.
+///
+/// If `force_input` is set, it is honored unconditionally. Otherwise, `Mmap`
+/// is preferred without checking platform support, except for small files,
+/// where the constant overhead of setting up a memory map is not worth it and
+/// `Owned` is chosen instead. The second element of the tuple is a fallback
+/// kind to use if the preferred kind turns out to be unusable at runtime.
+pub fn decide_input_strategy(
+    file: &FileOrStdin,
+    force_input: Option<&InputArg>,
+) -> eyre::Result<(ResolvedInputKind, Option<ResolvedInputKind>)> {
+    if let Some(forced) = force_input {
+        return Ok((forced.into(), None));
+    }
+
+    match file {
+        FileOrStdin::File(f) => {
+            let len = f.metadata()?.len();
+
+            if len < MMAP_SIZE_THRESHOLD {
+                Ok((ResolvedInputKind::Owned, None))
+            } else {
+                Ok((ResolvedInputKind::Mmap, Some(ResolvedInputKind::Owned)))
+            }
+        }
+        FileOrStdin::Stdin(_) => Ok((ResolvedInputKind::Mmap, Some(ResolvedInputKind::Owned))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn file_of_len(len: usize) -> File {
+        let mut file = tempfile::tempfile().expect("failed to create a temp file");
+        file.write_all(&vec![b' '; len]).expect("failed to write to the temp file");
+        file
+    }
+
+    #[test]
+    fn below_threshold_falls_back_to_owned() {
+        let file = FileOrStdin::File(file_of_len(MMAP_SIZE_THRESHOLD as usize - 1));
+        let (kind, fallback) = decide_input_strategy(&file, None).expect("strategy decision failed");
+
+        assert_eq!(kind, ResolvedInputKind::Owned);
+        assert_eq!(fallback, None);
+    }
+
+    #[test]
+    fn at_or_above_threshold_prefers_mmap() {
+        let file = FileOrStdin::File(file_of_len(MMAP_SIZE_THRESHOLD as usize));
+        let (kind, fallback) = decide_input_strategy(&file, None).expect("strategy decision failed");
+
+        assert_eq!(kind, ResolvedInputKind::Mmap);
+        assert_eq!(fallback, Some(ResolvedInputKind::Owned));
+    }
+
+    #[test]
+    fn force_input_overrides_the_size_based_decision() {
+        let file = FileOrStdin::File(file_of_len(0));
+        let (kind, fallback) = decide_input_strategy(&file, Some(&InputArg::Buffered)).expect("strategy decision failed");
+
+        assert_eq!(kind, ResolvedInputKind::Buffered);
+        assert_eq!(fallback, None);
+    }
+}
@@ -0,0 +1,114 @@
+//! Input implementation that copies the whole document into an owned, padded buffer.
+//!
+//! ## Performance characteristics
+//!
+//! Building this input requires copying the entire document once, but
+//! afterwards all accesses are plain slice operations with no syscall or
+//! page-fault overhead. For small documents this beats a memory map, since
+//! there is no page table setup/teardown cost to pay.
+
+use super::{error::InputError, in_slice, Input, InputBlockIterator, MAX_BLOCK_SIZE};
+use crate::{query::JsonString, FallibleIterator};
+
+/// Input holding the entire document as an owned, block-aligned, zero-padded buffer.
+pub struct OwnedBytes {
+    bytes: Vec<u8>,
+}
+
+impl OwnedBytes {
+    /// Copy `contents` into a new padded buffer.
+    ///
+    /// # Errors
+    ///
+    /// This constructor is currently infallible, but returns a `Result` to
+    /// match the other `Input` constructors and leave room for future
+    /// validation.
+    #[inline]
+    pub fn new(contents: &str) -> Result<Self, InputError> {
+        let raw = contents.as_bytes();
+        let rem = raw.len() % MAX_BLOCK_SIZE;
+        let pad = if rem == 0 { 0 } else { MAX_BLOCK_SIZE - rem };
+
+        let mut bytes = Vec::with_capacity(raw.len() + pad);
+        bytes.extend_from_slice(raw);
+        bytes.resize(raw.len() + pad, 0);
+
+        Ok(Self { bytes })
+    }
+}
+
+impl Input for OwnedBytes {
+    type BlockIterator<'a, const N: usize> = OwnedBytesBlockIterator<'a, N>;
+
+    #[inline(always)]
+    fn iter_blocks<const N: usize>(&self) -> Self::BlockIterator<'_, N> {
+        OwnedBytesBlockIterator::new(&self.bytes)
+    }
+
+    #[inline]
+    fn seek_backward(&self, from: usize, needle: u8) -> Option<usize> {
+        in_slice::seek_backward(&self.bytes, from, needle)
+    }
+
+    #[inline]
+    fn seek_non_whitespace_forward(&self, from: usize) -> Result<Option<(usize, u8)>, InputError> {
+        Ok(in_slice::seek_non_whitespace_forward(&self.bytes, from))
+    }
+
+    #[inline]
+    fn seek_non_whitespace_backward(&self, from: usize) -> Option<(usize, u8)> {
+        in_slice::seek_non_whitespace_backward(&self.bytes, from)
+    }
+
+    #[inline]
+    #[cfg(feature = "head-skip")]
+    fn find_member(&self, from: usize, label: &JsonString) -> Result<Option<usize>, InputError> {
+        Ok(in_slice::find_member(&self.bytes, from, label))
+    }
+
+    #[inline]
+    fn is_member_match(&self, from: usize, to: usize, label: &JsonString) -> bool {
+        in_slice::is_member_match(&self.bytes, from, to, label)
+    }
+}
+
+/// Iterator over blocks of [`OwnedBytes`] of size exactly `N`.
+pub struct OwnedBytesBlockIterator<'a, const N: usize> {
+    input: &'a [u8],
+    idx: usize,
+}
+
+impl<'a, const N: usize> OwnedBytesBlockIterator<'a, N> {
+    #[must_use]
+    #[inline(always)]
+    pub(super) fn new(bytes: &'a [u8]) -> Self {
+        Self { input: bytes, idx: 0 }
+    }
+}
+
+impl<'a, const N: usize> FallibleIterator for OwnedBytesBlockIterator<'a, N> {
+    type Item = &'a [u8];
+    type Error = InputError;
+
+    #[inline]
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        if self.idx >= self.input.len() {
+            Ok(None)
+        } else {
+            let block = &self.input[self.idx..self.idx + N];
+            self.idx += N;
+
+            Ok(Some(block))
+        }
+    }
+}
+
+impl<'a, const N: usize> InputBlockIterator<'a, N> for OwnedBytesBlockIterator<'a, N> {
+    type Block = &'a [u8];
+
+    #[inline(always)]
+    fn offset(&mut self, count: isize) {
+        assert!(count >= 0);
+        self.idx += count as usize * N;
+    }
+}
@@ -0,0 +1,126 @@
+//! CLI-level input handling: resolving the input strategy for a file or
+//! standard input, and detecting/decompressing compressed inputs.
+//!
+//! Compression codecs are feature-gated so that the binary only links the
+//! decoders the user actually asked for at build time.
+
+use std::io::{self, Read};
+use std::path::Path;
+
+pub use rsonpath_lib::input::{decide_input_strategy, FileOrStdin, InputArg, PeekableStdin, ResolvedInputKind};
+
+/// A compression codec recognized by the CLI's input detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip, detected by the `.gz` extension or the `\x1f\x8b` magic bytes.
+    Gzip,
+    /// Zstandard, detected by the `.zst` extension or its magic number.
+    Zstd,
+    /// bzip2, detected by the `.bz2` extension or the `BZh` magic bytes.
+    Bzip2,
+}
+
+impl Compression {
+    /// Detect a compression codec from a file path's extension.
+    #[must_use]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Some(Self::Gzip),
+            Some("zst") => Some(Self::Zstd),
+            Some("bz2") => Some(Self::Bzip2),
+            _ => None,
+        }
+    }
+
+    /// Detect a compression codec from the magic bytes at the start of the input.
+    #[must_use]
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else if bytes.starts_with(b"BZh") {
+            Some(Self::Bzip2)
+        } else {
+            None
+        }
+    }
+
+    /// Wrap `reader` in the streaming decoder for this codec.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the codec's decoder was not compiled in, or if the
+    /// underlying decoder fails to initialize. The stream itself is not read
+    /// here, so malformed data is only reported by reads from the decoder.
+    pub fn decode<'r, R: Read + 'r>(self, reader: R) -> eyre::Result<Box<dyn Read + 'r>> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Ok(Box::new(flate2::read::GzDecoder::new(reader))),
+            #[cfg(not(feature = "gzip"))]
+            Self::Gzip => Err(Self::unsupported_codec_error("gzip", "gzip")),
+
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Ok(Box::new(zstd::stream::Decoder::new(reader)?)),
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd => Err(Self::unsupported_codec_error("zstd", "zstd")),
+
+            #[cfg(feature = "bzip2")]
+            Self::Bzip2 => Ok(Box::new(bzip2::read::BzDecoder::new(reader))),
+            #[cfg(not(feature = "bzip2"))]
+            Self::Bzip2 => Err(Self::unsupported_codec_error("bzip2", "bzip2")),
+        }
+    }
+
+    #[allow(dead_code)]
+    fn unsupported_codec_error(name: &str, feature: &str) -> eyre::Report {
+        eyre::eyre!("{name} support is not compiled into this binary; rebuild with `--features {feature}`")
+    }
+}
+
+/// Decompress `bytes` fully according to `compression` and return the result
+/// as a UTF-8 string.
+///
+/// # Errors
+///
+/// Returns an error if the decoder fails, or if the decompressed bytes are
+/// not valid UTF-8.
+pub fn decompress_to_string(compression: Compression, bytes: Vec<u8>) -> eyre::Result<String> {
+    let mut decoder = compression.decode(io::Cursor::new(bytes))?;
+    let mut out = Vec::new();
+    decoder.read_to_end(&mut out)?;
+
+    Ok(String::from_utf8(out)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_path_detects_known_extensions() {
+        assert_eq!(Compression::from_path("data.json.gz"), Some(Compression::Gzip));
+        assert_eq!(Compression::from_path("data.json.zst"), Some(Compression::Zstd));
+        assert_eq!(Compression::from_path("data.json.bz2"), Some(Compression::Bzip2));
+        assert_eq!(Compression::from_path("data.json"), None);
+    }
+
+    #[test]
+    fn from_magic_bytes_detects_known_codecs() {
+        assert_eq!(Compression::from_magic_bytes(&[0x1f, 0x8b, 0x08, 0x00]), Some(Compression::Gzip));
+        assert_eq!(
+            Compression::from_magic_bytes(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::from_magic_bytes(b"BZh91AY"), Some(Compression::Bzip2));
+        assert_eq!(Compression::from_magic_bytes(b"{\"key\":1}"), None);
+    }
+
+    #[test]
+    fn from_magic_bytes_ignores_extension_when_sniffing() {
+        // A file that was renamed or piped without its original extension
+        // should still be recognized from its magic bytes alone.
+        assert_eq!(Compression::from_path("data.txt"), None);
+        assert_eq!(Compression::from_magic_bytes(&[0x1f, 0x8b]), Some(Compression::Gzip));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::input::{self, FileOrStdin, ResolvedInputKind};
+use crate::input::{self, Compression, FileOrStdin, PeekableStdin, ResolvedInputKind};
 use crate::{
     args::{EngineArg, InputArg, ResultArg},
     error::report_engine_error,
@@ -12,52 +12,220 @@ use rsonpath_lib::{
     result::{CountResult, IndexResult},
 };
 use std::{
+    collections::{HashSet, VecDeque},
     fs,
-    io::{self, Read},
-    path::Path,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
 };
 
+/// An input still waiting to be opened: either standard input, or a file path
+/// that hasn't been opened yet.
+///
+/// Kept separate from [`ResolvedInput`] so that a batch of many inputs can be
+/// queued up front without holding a file descriptor open per input; each is
+/// only opened once a worker actually picks it up.
+pub enum PendingInput {
+    /// Standard input.
+    Stdin,
+    /// An as-yet-unopened file.
+    File(PathBuf),
+}
+
 pub struct Runner<'q> {
     pub with_compiled_query: Automaton<'q>,
     pub with_engine: ResolvedEngine,
-    pub with_input: ResolvedInput,
+    pub with_inputs: Vec<PendingInput>,
+    pub with_force_input: Option<InputArg>,
     pub with_output: ResolvedOutput,
 }
 
 impl<'q> Runner<'q> {
     pub fn run(self) -> Result<()> {
-        match self.with_engine {
-            ResolvedEngine::Recursive => {
-                let engine = RecursiveEngine::from_compiled_query(self.with_compiled_query);
-                self.with_input
-                    .run_engine(engine, self.with_output)
-                    .wrap_err("Error running the recursive engine.")
-            }
-            ResolvedEngine::Main => {
-                let engine = MainEngine::from_compiled_query(self.with_compiled_query);
-                self.with_input
-                    .run_engine(engine, self.with_output)
-                    .wrap_err("Error running the main engine.")
-            }
+        // Multiple inputs are labelled with their originating path so results
+        // stay attributable once interleaved; a single input keeps the plain,
+        // unlabelled output for backwards compatibility.
+        let multi = self.with_inputs.len() > 1;
+        let query = &self.with_compiled_query;
+        let engine_kind = self.with_engine;
+        let output = self.with_output;
+        let force_input = self.with_force_input;
+
+        // Bound concurrency to the available parallelism rather than spawning
+        // one thread per input: a directory with thousands of files would
+        // otherwise blow through the process's thread (and, transitively,
+        // file descriptor) limits. Workers pull from a shared queue and each
+        // opens its input lazily, so at most `worker_count` files are open at
+        // once; apart from that queue, the shared compiled automaton is the
+        // only cross-thread state.
+        let worker_count = thread::available_parallelism()
+            .map(std::num::NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(self.with_inputs.len().max(1));
+        let queue = Mutex::new(VecDeque::from(self.with_inputs));
+
+        let results: Vec<Result<()>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..worker_count)
+                .map(|_| {
+                    let queue = &queue;
+                    let force_input = force_input;
+
+                    scope.spawn(move || -> Vec<Result<()>> {
+                        let mut worker_results = Vec::new();
+
+                        while let Some(pending) = queue.lock().expect("queue mutex poisoned").pop_front() {
+                            let outcome = open_pending_input(pending, force_input.as_ref())
+                                .and_then(|input| run_one(engine_kind, query, output, input, multi));
+                            worker_results.push(outcome);
+                        }
+
+                        worker_results
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("a worker thread panicked"))
+                .collect()
+        });
+
+        results.into_iter().collect::<Result<Vec<()>>>()?;
+
+        Ok(())
+    }
+}
+
+fn open_pending_input(pending: PendingInput, force_input: Option<&InputArg>) -> Result<ResolvedInput> {
+    match pending {
+        PendingInput::Stdin => resolve_input(None::<&Path>, force_input),
+        PendingInput::File(path) => resolve_input(Some(path), force_input),
+    }
+}
+
+fn run_one(engine_kind: ResolvedEngine, query: &Automaton<'_>, output: ResolvedOutput, input: ResolvedInput, multi: bool) -> Result<()> {
+    match engine_kind {
+        ResolvedEngine::Recursive => {
+            let engine = RecursiveEngine::from_compiled_query(query.clone());
+            input
+                .run_engine(engine, output, multi)
+                .wrap_err("Error running the recursive engine.")
+        }
+        ResolvedEngine::Main => {
+            let engine = MainEngine::from_compiled_query(query.clone());
+            input
+                .run_engine(engine, output, multi)
+                .wrap_err("Error running the main engine.")
         }
     }
 }
 
 pub fn resolve_input<P: AsRef<Path>>(file_path: Option<P>, force_input: Option<&InputArg>) -> Result<ResolvedInput> {
-    let file = match file_path {
+    let origin = file_path.as_ref().map(|p| p.as_ref().to_path_buf());
+    let extension_compression = file_path.as_ref().and_then(Compression::from_path);
+
+    let mut file = match file_path {
         Some(path) => FileOrStdin::File(fs::File::open(path).wrap_err("Error reading the provided file.")?),
-        None => FileOrStdin::Stdin(io::stdin()),
+        None => FileOrStdin::Stdin(PeekableStdin::new()),
     };
 
-    let (kind, fallback_kind) = input::decide_input_strategy(&file, force_input)?;
+    // The extension can miss a compressed input with no recognized suffix
+    // (e.g. piped through a FIFO, or renamed without `.gz`), so sniff the
+    // magic bytes up front too, before the input-strategy decision below
+    // runs. A file is rewound afterwards; stdin's peeked bytes are replayed
+    // transparently by `PeekableStdin` for whichever strategy ends up
+    // reading it.
+    let compression = match extension_compression {
+        Some(codec) => Some(codec),
+        None => sniff_compression(&mut file).wrap_err("Error sniffing the input for a compression header.")?,
+    };
+
+    // A compressed input only ever makes sense decoded into memory, so absent
+    // an explicit `--input` override it resolves straight to `Owned`,
+    // bypassing the usual mmap/buffered decision. An explicit `force_input`
+    // is still honored unconditionally, as `decide_input_strategy` documents;
+    // forcing `Mmap`/`Buffered` on a compressed input surfaces as an error
+    // once `run_engine` gets to it.
+    let (kind, fallback_kind) = if force_input.is_none() && compression.is_some() {
+        (ResolvedInputKind::Owned, None)
+    } else {
+        input::decide_input_strategy(&file, force_input)?
+    };
 
     Ok(ResolvedInput {
         file,
         kind,
         fallback_kind,
+        compression,
+        origin,
     })
 }
 
+/// Peek at the first few bytes of `file` to detect a compression header
+/// without losing them for later reads. A file is read and immediately
+/// rewound to the start; stdin's peeked bytes are retained and replayed by
+/// [`PeekableStdin`], since a stream can't be seeked back.
+fn sniff_compression(file: &mut FileOrStdin) -> Result<Option<Compression>> {
+    /// Longest magic number among the recognized codecs (zstd's, at 4 bytes).
+    const MAGIC_LEN: usize = 4;
+
+    match file {
+        FileOrStdin::File(f) => {
+            let mut buf = [0; MAGIC_LEN];
+            let read = f.read(&mut buf).wrap_err("Error reading the provided file.")?;
+            f.seek(SeekFrom::Start(0)).wrap_err("Error rewinding the provided file.")?;
+
+            Ok(Compression::from_magic_bytes(&buf[..read]))
+        }
+        FileOrStdin::Stdin(stdin) => {
+            let peeked = stdin.peek(MAGIC_LEN).wrap_err("Error reading from standard input.")?;
+
+            Ok(Compression::from_magic_bytes(peeked))
+        }
+    }
+}
+
+/// Resolve a batch of input paths, expanding any directories into the files
+/// they (recursively) contain. Paths are only collected here, not opened;
+/// each is opened lazily by the worker that ends up querying it, so this
+/// holds open only the directories on the current walk path, regardless of
+/// how many files the batch contains.
+///
+/// Used to turn rsonpath into a grep-like batch tool: the compiled query is
+/// shared across all of them, but each gets its own input strategy.
+pub fn resolve_inputs<P: AsRef<Path>>(file_paths: Vec<P>) -> Result<Vec<PendingInput>> {
+    let mut paths = Vec::new();
+    let mut visited_dirs = HashSet::new();
+    for path in &file_paths {
+        collect_paths(path.as_ref(), &mut paths, &mut visited_dirs)?;
+    }
+
+    Ok(paths.into_iter().map(PendingInput::File).collect())
+}
+
+fn collect_paths(path: &Path, out: &mut Vec<PathBuf>, visited_dirs: &mut HashSet<PathBuf>) -> Result<()> {
+    let metadata = fs::metadata(path).wrap_err("Error reading the provided path.")?;
+
+    if metadata.is_dir() {
+        // `metadata` follows symlinks, so a symlinked directory resolves to
+        // the same canonical path as its target; tracking canonical paths
+        // here turns a symlink cycle into a no-op instead of infinite recursion.
+        let canonical = fs::canonicalize(path).wrap_err("Error resolving the provided directory.")?;
+        if !visited_dirs.insert(canonical) {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(path).wrap_err("Error reading the provided directory.")? {
+            collect_paths(&entry.wrap_err("Error reading a directory entry.")?.path(), out, visited_dirs)?;
+        }
+    } else {
+        out.push(path.to_path_buf());
+    }
+
+    Ok(())
+}
+
 pub fn resolve_output(result_arg: ResultArg) -> ResolvedOutput {
     match result_arg {
         ResultArg::Bytes => ResolvedOutput::Index,
@@ -72,6 +240,7 @@ pub fn resolve_engine(engine_arg: EngineArg) -> ResolvedEngine {
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum ResolvedEngine {
     Recursive,
     Main,
@@ -81,78 +250,197 @@ pub struct ResolvedInput {
     file: FileOrStdin,
     kind: ResolvedInputKind,
     fallback_kind: Option<ResolvedInputKind>,
+    compression: Option<Compression>,
+    /// The path this input was read from, if any (absent for stdin). Used to
+    /// label results when querying more than one input at a time.
+    origin: Option<PathBuf>,
 }
 
+#[derive(Clone, Copy)]
 pub enum ResolvedOutput {
     Count,
     Index,
 }
 
 impl ResolvedInput {
-    fn run_engine<E: Engine>(self, engine: E, with_output: ResolvedOutput) -> Result<()> {
+    fn run_engine<E: Engine>(mut self, engine: E, with_output: ResolvedOutput, multi: bool) -> Result<()> {
+        let label = multi.then(|| match &self.origin {
+            Some(path) => path.display().to_string(),
+            None => "<stdin>".to_string(),
+        });
+
         match self.kind {
             ResolvedInputKind::Mmap => {
-                let mmap_result = match &self.file {
-                    FileOrStdin::File(f) => unsafe { MmapInput::map_file(f) },
-                    FileOrStdin::Stdin(_) => todo!(),
-                };
-
-                match mmap_result {
-                    Ok(input) => with_output.run_and_output(engine, input),
-                    Err(err) => match self.fallback_kind {
-                        Some(fallback_kind) => {
-                            warn!(
-                                "Creating a memory map failed: '{}'. Falling back to a slower input strategy.",
-                                err
-                            );
-                            let new_input = ResolvedInput {
-                                kind: fallback_kind,
-                                fallback_kind: None,
-                                file: self.file,
-                            };
-
-                            new_input.run_engine(engine, with_output)
-                        }
-                        None => Err(err).wrap_err("Creating a memory map failed."),
+                if let Some(compression) = self.compression {
+                    return Err(unsupported_compressed_combination(compression, "a memory map"));
+                }
+
+                match self.file {
+                    FileOrStdin::File(f) => match unsafe { MmapInput::map_file_with_advice(&f) } {
+                        Ok(input) => with_output.run_and_output(engine, input, label.as_deref()),
+                        Err(err) => match self.fallback_kind {
+                            Some(fallback_kind) => {
+                                warn!(
+                                    "Creating a memory map failed: '{}'. Falling back to a slower input strategy.",
+                                    err
+                                );
+                                let new_input = ResolvedInput {
+                                    kind: fallback_kind,
+                                    fallback_kind: None,
+                                    file: FileOrStdin::File(f),
+                                    compression: self.compression,
+                                    origin: self.origin,
+                                };
+
+                                new_input.run_engine(engine, with_output, multi)
+                            }
+                            None => Err(err).wrap_err("Creating a memory map failed."),
+                        },
                     },
+                    FileOrStdin::Stdin(mut stdin) => {
+                        // Unlike a file, stdin can't be mapped without first
+                        // draining it into a buffer, so that buffer is the
+                        // only copy of the input; if mapping it then fails,
+                        // the fallback below must reuse these same bytes
+                        // rather than reading the now-exhausted stream again.
+                        let mut buf = Vec::new();
+                        stdin.read_to_end(&mut buf).wrap_err("Reading from standard input failed.")?;
+
+                        match MmapInput::from_bytes(&buf) {
+                            Ok(input) => {
+                                // The mapping has its own copy of the bytes now, so
+                                // the buffer isn't needed for the rest of the query
+                                // and shouldn't double the input's peak memory use.
+                                drop(buf);
+                                with_output.run_and_output(engine, input, label.as_deref())
+                            }
+                            Err(err) => match self.fallback_kind {
+                                Some(ResolvedInputKind::Owned) => {
+                                    warn!(
+                                        "Creating a memory map failed: '{}'. Falling back to a slower input strategy.",
+                                        err
+                                    );
+                                    let contents = String::from_utf8(buf).wrap_err("Input is not valid UTF-8.")?;
+                                    let input = OwnedBytes::new(&contents)?;
+
+                                    with_output.run_and_output(engine, input, label.as_deref())
+                                }
+                                Some(fallback_kind) => {
+                                    unreachable!("decide_input_strategy never pairs Mmap with a {fallback_kind:?} fallback")
+                                }
+                                None => Err(err).wrap_err("Creating a memory map failed."),
+                            },
+                        }
+                    }
                 }
             }
             ResolvedInputKind::Owned => {
-                let contents = get_contents(self.file)?;
+                let contents = get_contents(self.file, self.compression)?;
                 let input = OwnedBytes::new(&contents)?;
-                with_output.run_and_output(engine, input)
+                with_output.run_and_output(engine, input, label.as_deref())
             }
             ResolvedInputKind::Buffered => {
+                if let Some(compression) = self.compression {
+                    return Err(unsupported_compressed_combination(compression, "the buffered reader"));
+                }
+
                 let input = BufferedInput::new(self.file);
-                with_output.run_and_output(engine, input)
+                with_output.run_and_output(engine, input, label.as_deref())
             }
         }
     }
 }
 
+fn unsupported_compressed_combination(compression: Compression, strategy: &str) -> eyre::Report {
+    eyre::eyre!(
+        "compressed input ({compression:?}) cannot be used with {strategy}; it is only supported with the owned input strategy"
+    )
+}
+
 impl ResolvedOutput {
-    fn run_and_output<E: Engine, I: Input>(self, engine: E, input: I) -> Result<()> {
-        fn run_impl<E: Engine, I: Input>(out: ResolvedOutput, engine: E, input: I) -> Result<(), EngineError> {
-            match out {
+    fn run_and_output<E: Engine, I: Input>(self, engine: E, input: I, label: Option<&str>) -> Result<()> {
+        fn run_impl<E: Engine, I: Input>(out: ResolvedOutput, engine: E, input: I) -> Result<String, EngineError> {
+            let rendered = match out {
                 ResolvedOutput::Count => {
                     let result = engine.run::<_, CountResult>(&input)?;
-                    print!("{result}");
+                    format!("{result}")
                 }
                 ResolvedOutput::Index => {
                     let result = engine.run::<_, IndexResult>(&input)?;
-                    print!("{result}");
+                    format!("{result}")
                 }
-            }
+            };
 
-            Ok(())
+            Ok(rendered)
         }
 
-        run_impl(self, engine, input).map_err(|err| report_engine_error(err).wrap_err("Error executing the query."))
+        let rendered = run_impl(self, engine, input)
+            .map_err(|err| report_engine_error(err).wrap_err("Error executing the query."))?;
+
+        // Prefix each line with its originating path when running against
+        // more than one input, like a batch grep; otherwise keep the plain
+        // single-input output.
+        match label {
+            Some(path) => {
+                for line in rendered.lines() {
+                    println!("{path}:{line}");
+                }
+            }
+            None => print!("{rendered}"),
+        }
+
+        Ok(())
+    }
+}
+
+fn get_contents(mut file: FileOrStdin, compression: Option<Compression>) -> Result<String> {
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw).wrap_err("Reading from file failed.")?;
+
+    // `resolve_input` normally passes the codec it already detected from the
+    // extension or the magic bytes; if `compression` is `None`, the magic
+    // bytes are checked once more here now that the raw bytes are in hand.
+    match compression.or_else(|| Compression::from_magic_bytes(&raw)) {
+        Some(codec) => input::decompress_to_string(codec, raw).wrap_err("Decompressing input failed."),
+        None => String::from_utf8(raw).wrap_err("Input is not valid UTF-8."),
     }
 }
 
-fn get_contents(mut file: FileOrStdin) -> Result<String> {
-    let mut result = String::new();
-    file.read_to_string(&mut result).wrap_err("Reading from file failed.")?;
-    Ok(result)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_paths_expands_a_directory_recursively() {
+        let dir = tempfile::tempdir().expect("failed to create a temp dir");
+        fs::write(dir.path().join("a.json"), "{}").unwrap();
+        let nested = dir.path().join("nested");
+        fs::create_dir(&nested).unwrap();
+        fs::write(nested.join("b.json"), "{}").unwrap();
+
+        let mut out = Vec::new();
+        let mut visited = HashSet::new();
+        collect_paths(dir.path(), &mut out, &mut visited).expect("collecting paths failed");
+
+        assert_eq!(out.len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn collect_paths_does_not_loop_forever_on_a_symlink_cycle() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::tempdir().expect("failed to create a temp dir");
+        fs::write(dir.path().join("a.json"), "{}").unwrap();
+        let cycle = dir.path().join("cycle");
+        symlink(dir.path(), &cycle).expect("failed to create a symlink");
+
+        let mut out = Vec::new();
+        let mut visited = HashSet::new();
+        collect_paths(dir.path(), &mut out, &mut visited).expect("collecting paths failed");
+
+        // The real file is only found once, even though the symlink cycle
+        // revisits the same directory.
+        assert_eq!(out.len(), 1);
+    }
 }